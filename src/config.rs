@@ -2,17 +2,41 @@ use super::banner::BANNER;
 use anyhow::{anyhow, Error, Result};
 use serde::{Deserialize, Serialize};
 use std::{
+  collections::HashSet,
   fs,
   io::{stdin, Write},
+  net::{IpAddr, Ipv4Addr},
   path::{Path, PathBuf},
 };
 
 const DEFAULT_PORT: u16 = 8888;
+const DEFAULT_BIND_ADDR: Ipv4Addr = Ipv4Addr::LOCALHOST;
 const FILE_NAME: &str = "client.yml";
 const CONFIG_DIR: &str = ".config";
 const APP_CONFIG_DIR: &str = "spotatui";
 const TOKEN_CACHE_FILE: &str = ".spotify_token_cache.json";
 
+const DEFAULT_SCOPES: [&str; 14] = [
+  "playlist-read-collaborative",
+  "playlist-read-private",
+  "playlist-modify-private",
+  "playlist-modify-public",
+  "user-follow-read",
+  "user-follow-modify",
+  "user-library-modify",
+  "user-library-read",
+  "user-modify-playback-state",
+  "user-read-currently-playing",
+  "user-read-playback-state",
+  "user-read-playback-position",
+  "user-read-private",
+  "user-read-recently-played",
+];
+
+// Without these the app can neither show nor control playback, so a custom
+// `scopes` list must always include them
+const REQUIRED_SCOPES: [&str; 2] = ["user-read-playback-state", "user-modify-playback-state"];
+
 #[derive(Default, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ClientConfig {
   pub client_id: String,
@@ -20,6 +44,11 @@ pub struct ClientConfig {
   pub device_id: Option<String>,
   // FIXME: port should be defined in `user_config` not in here
   pub port: Option<u16>,
+  // Overrides the default OAuth scopes when set
+  pub scopes: Option<Vec<String>>,
+  // IPv4 address the OAuth callback server listens on, defaults to loopback only.
+  // Must be IPv4 since the redirect uri always points at 127.0.0.1
+  pub bind_addr: Option<String>,
 }
 
 pub struct ConfigPaths {
@@ -34,6 +63,8 @@ impl ClientConfig {
       client_secret: "".to_string(),
       device_id: None,
       port: None,
+      scopes: None,
+      bind_addr: None,
     }
   }
 
@@ -45,6 +76,55 @@ impl ClientConfig {
     self.port.unwrap_or(DEFAULT_PORT)
   }
 
+  pub fn get_bind_addr(&self) -> Result<Ipv4Addr> {
+    let bind_addr = match &self.bind_addr {
+      Some(bind_addr) => bind_addr.trim(),
+      None => return Ok(DEFAULT_BIND_ADDR),
+    };
+
+    match bind_addr.parse::<IpAddr>() {
+      Ok(IpAddr::V4(addr)) => Ok(addr),
+      Ok(IpAddr::V6(_)) => Err(anyhow!(
+        "`bind_addr` in {} must be an IPv4 address, got {}: the redirect uri always points at 127.0.0.1",
+        FILE_NAME,
+        bind_addr
+      )),
+      Err(_) => Err(anyhow!(
+        "`bind_addr` in {} must be an IPv4 address such as 127.0.0.1 or 0.0.0.0, got {}",
+        FILE_NAME,
+        bind_addr
+      )),
+    }
+  }
+
+  pub fn get_scopes(&self) -> Result<HashSet<String>> {
+    let scopes: HashSet<String> = match &self.scopes {
+      Some(scopes) => {
+        if scopes.iter().any(|s| s.trim().is_empty()) {
+          return Err(anyhow!("`scopes` in {} contains an empty scope", FILE_NAME));
+        }
+        scopes.iter().map(|s| s.trim().to_string()).collect()
+      }
+      None => DEFAULT_SCOPES.iter().map(|s| s.to_string()).collect(),
+    };
+
+    let missing: Vec<&str> = REQUIRED_SCOPES
+      .iter()
+      .filter(|scope| !scopes.contains(**scope))
+      .copied()
+      .collect();
+
+    if missing.is_empty() {
+      Ok(scopes)
+    } else {
+      Err(anyhow!(
+        "`scopes` in {} is missing required scope(s): {}",
+        FILE_NAME,
+        missing.join(", ")
+      ))
+    }
+  }
+
   pub fn get_or_build_paths(&self) -> Result<ConfigPaths> {
     match dirs::home_dir() {
       Some(home) => {
@@ -98,6 +178,11 @@ impl ClientConfig {
       self.client_secret = config_yml.client_secret;
       self.device_id = config_yml.device_id;
       self.port = config_yml.port;
+      self.scopes = config_yml.scopes;
+      self.bind_addr = config_yml.bind_addr;
+
+      // Fail on a bad `bind_addr` now rather than halfway through login
+      self.get_bind_addr()?;
 
       Ok(())
     } else {
@@ -140,6 +225,8 @@ impl ClientConfig {
         client_secret,
         device_id: None,
         port: Some(port),
+        scopes: None,
+        bind_addr: None,
       };
 
       let content_yml = serde_yaml::to_string(&config_yml)?;
@@ -151,6 +238,8 @@ impl ClientConfig {
       self.client_secret = config_yml.client_secret;
       self.device_id = config_yml.device_id;
       self.port = config_yml.port;
+      self.scopes = config_yml.scopes;
+      self.bind_addr = config_yml.bind_addr;
 
       Ok(())
     }
@@ -198,3 +287,77 @@ impl ClientConfig {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_get_bind_addr() {
+    let mut config = ClientConfig::new();
+    assert_eq!(config.get_bind_addr().unwrap(), Ipv4Addr::LOCALHOST);
+
+    config.bind_addr = Some("0.0.0.0".to_string());
+    assert_eq!(config.get_bind_addr().unwrap(), Ipv4Addr::UNSPECIFIED);
+
+    config.bind_addr = Some("::1".to_string());
+    assert!(config.get_bind_addr().is_err());
+
+    config.bind_addr = Some("localhost".to_string());
+    assert!(config.get_bind_addr().is_err());
+  }
+
+  #[test]
+  fn test_get_scopes_defaults() {
+    let config = ClientConfig::new();
+    let scopes = config.get_scopes().unwrap();
+    assert_eq!(scopes.len(), DEFAULT_SCOPES.len());
+    for scope in DEFAULT_SCOPES.iter().chain(REQUIRED_SCOPES.iter()) {
+      assert!(scopes.contains(*scope));
+    }
+  }
+
+  #[test]
+  fn test_get_scopes_override() {
+    let mut config = ClientConfig::new();
+    config.scopes = Some(vec![
+      "user-read-playback-state".to_string(),
+      "user-modify-playback-state".to_string(),
+      "user-library-read".to_string(),
+    ]);
+    let scopes = config.get_scopes().unwrap();
+    assert_eq!(scopes.len(), 3);
+    assert!(!scopes.contains("playlist-modify-public"));
+  }
+
+  #[test]
+  fn test_get_scopes_trims_and_dedups() {
+    let mut config = ClientConfig::new();
+    config.scopes = Some(vec![
+      " user-read-playback-state ".to_string(),
+      " user-read-playback-state ".to_string(),
+      "user-modify-playback-state".to_string(),
+    ]);
+    let scopes = config.get_scopes().unwrap();
+    assert_eq!(scopes.len(), 2);
+    assert!(scopes.contains("user-read-playback-state"));
+  }
+
+  #[test]
+  fn test_get_scopes_rejects_empty() {
+    let mut config = ClientConfig::new();
+    config.scopes = Some(vec![
+      "user-read-playback-state".to_string(),
+      "user-modify-playback-state".to_string(),
+      " ".to_string(),
+    ]);
+    assert!(config.get_scopes().is_err());
+  }
+
+  #[test]
+  fn test_get_scopes_missing_required() {
+    let mut config = ClientConfig::new();
+    config.scopes = Some(vec!["user-library-read".to_string()]);
+    assert!(config.get_scopes().is_err());
+  }
+}
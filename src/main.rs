@@ -50,23 +50,6 @@ use std::{
 use tokio::sync::Mutex;
 use user_config::{UserConfig, UserConfigPaths};
 
-const SCOPES: [&str; 14] = [
-  "playlist-read-collaborative",
-  "playlist-read-private",
-  "playlist-modify-private",
-  "playlist-modify-public",
-  "user-follow-read",
-  "user-follow-modify",
-  "user-library-modify",
-  "user-library-read",
-  "user-modify-playback-state",
-  "user-read-currently-playing",
-  "user-read-playback-state",
-  "user-read-playback-position",
-  "user-read-private",
-  "user-read-recently-played",
-];
-
 // Manual token cache helpers since rspotify's built-in caching isn't working
 async fn save_token_to_file(spotify: &AuthCodeSpotify, path: &PathBuf) -> Result<()> {
   let token_lock = spotify.token.lock().await.expect("Failed to lock token");
@@ -94,6 +77,22 @@ async fn load_token_from_file(spotify: &AuthCodeSpotify, path: &PathBuf) -> Resu
   Ok(true)
 }
 
+// A redirect uri that doesn't match the one registered for the client id is
+// the most common cause of failed logins, so include both in auth errors
+fn auth_context(spotify: &AuthCodeSpotify) -> String {
+  format!(
+    "redirect URI: {}, client ID: {}",
+    spotify.oauth.redirect_uri, spotify.creds.id
+  )
+}
+
+async fn request_token_with_context(spotify: &AuthCodeSpotify, code: &str) -> Result<()> {
+  spotify
+    .request_token(code)
+    .await
+    .map_err(|e| anyhow!("Failed to request token: {} ({})", e, auth_context(spotify)))
+}
+
 fn close_application() -> Result<()> {
   disable_raw_mode()?;
   let mut stdout = io::stdout();
@@ -317,7 +316,7 @@ of the app. Beware that this comes at a CPU cost!",
 
   let oauth = OAuth {
     redirect_uri: client_config.get_redirect_uri(),
-    scopes: SCOPES.iter().map(|s| s.to_string()).collect(),
+    scopes: client_config.get_scopes()?,
     ..Default::default()
   };
 
@@ -357,21 +356,33 @@ of the app. Beware that this comes at a CPU cost!",
       println!("Please manually open the URL above in your browser.");
     }
 
+    let bind_addr = client_config.get_bind_addr()?;
+    if !bind_addr.is_loopback() {
+      println!(
+        "⚠ Warning: the callback server is bound to {}, which exposes it to your network.",
+        bind_addr
+      );
+      println!("  Only use this when spotatui runs in docker with the callback port published.\n");
+    }
+
     println!(
-      "Waiting for authorization callback on http://127.0.0.1:{}...\n",
+      "Waiting for authorization callback on {} (listening on {}:{})...\n",
+      client_config.get_redirect_uri(),
+      bind_addr,
       config_port
     );
 
-    match redirect_uri_web_server(&mut spotify, config_port) {
+    match redirect_uri_web_server(&mut spotify, bind_addr, config_port) {
       Ok(url) => {
         if let Some(code) = spotify.parse_response_code(&url) {
-          spotify.request_token(&code).await?;
+          request_token_with_context(&spotify, &code).await?;
           // Write the token to our manual cache
           save_token_to_file(&spotify, &config_paths.token_cache_path).await?;
           println!("✓ Successfully authenticated with Spotify!");
         } else {
           return Err(anyhow!(
-            "Failed to parse authorization code from callback URL"
+            "Failed to parse authorization code from callback URL ({})",
+            auth_context(&spotify)
           ));
         }
       }
@@ -382,11 +393,14 @@ of the app. Beware that this comes at a CPU cost!",
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
         if let Some(code) = spotify.parse_response_code(&input) {
-          spotify.request_token(&code).await?;
+          request_token_with_context(&spotify, &code).await?;
           // Write the token to our manual cache
           save_token_to_file(&spotify, &config_paths.token_cache_path).await?;
         } else {
-          return Err(anyhow!("Failed to parse authorization code from input URL"));
+          return Err(anyhow!(
+            "Failed to parse authorization code from input URL ({})",
+            auth_context(&spotify)
+          ));
         }
       }
     }
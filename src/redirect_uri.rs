@@ -1,18 +1,23 @@
 use rspotify::AuthCodeSpotify;
 use std::{
   io::prelude::*,
-  net::{TcpListener, TcpStream},
+  net::{Ipv4Addr, TcpListener, TcpStream},
 };
 
-pub fn redirect_uri_web_server(_spotify: &mut AuthCodeSpotify, port: u16) -> Result<String, ()> {
-  let listener = TcpListener::bind(format!("127.0.0.1:{}", port));
+pub fn redirect_uri_web_server(
+  spotify: &mut AuthCodeSpotify,
+  bind_addr: Ipv4Addr,
+  port: u16,
+) -> Result<String, ()> {
+  let listener = TcpListener::bind((bind_addr, port));
+  let callback_path = redirect_path(&spotify.oauth.redirect_uri).to_string();
 
   match listener {
     Ok(listener) => {
       for stream in listener.incoming() {
         match stream {
           Ok(stream) => {
-            if let Some(url) = handle_connection(stream) {
+            if let Some(url) = handle_connection(stream, &callback_path) {
               return Ok(url);
             }
           }
@@ -30,10 +35,34 @@ pub fn redirect_uri_web_server(_spotify: &mut AuthCodeSpotify, port: u16) -> Res
   Err(())
 }
 
-fn handle_connection(mut stream: TcpStream) -> Option<String> {
+// Path component of the redirect uri, e.g. "/callback" for "http://127.0.0.1:8888/callback"
+fn redirect_path(redirect_uri: &str) -> &str {
+  let without_scheme = redirect_uri
+    .split_once("://")
+    .map_or(redirect_uri, |(_, rest)| rest);
+
+  match without_scheme.find('/') {
+    Some(index) => {
+      let path = &without_scheme[index..];
+      path.split(['?', '#']).next().unwrap_or(path)
+    }
+    None => "/",
+  }
+}
+
+fn is_callback_request(request_path: &str, callback_path: &str) -> bool {
+  request_path.split('?').next() == Some(callback_path)
+}
+
+fn handle_connection(mut stream: TcpStream, callback_path: &str) -> Option<String> {
   // The request will be quite large (> 512) so just assign plenty just in case
   let mut buffer = [0; 1000];
-  let _ = stream.read(&mut buffer).unwrap();
+  // With a non-loopback bind anyone on the network can connect, so a reset
+  // connection must not panic the app mid-login
+  if let Err(e) = stream.read(&mut buffer) {
+    println!("Error: {}", e);
+    return None;
+  }
 
   // convert buffer into string and 'parse' the URL
   match String::from_utf8(buffer.to_vec()) {
@@ -44,6 +73,13 @@ fn handle_connection(mut stream: TcpStream) -> Option<String> {
         // Extract the path from the HTTP request (e.g., "/callback?code=...&state=...")
         let path = split[1];
 
+        // Browsers also ask for things like /favicon.ico, which must not be
+        // mistaken for the callback
+        if !is_callback_request(path, callback_path) {
+          respond_with_not_found(stream);
+          return None;
+        }
+
         // Parse the host header to build the full URL
         let host = request
           .lines()
@@ -78,12 +114,24 @@ fn respond_with_success(mut stream: TcpStream) {
     contents
   );
 
-  stream.write_all(response.as_bytes()).unwrap();
-  stream.flush().unwrap();
+  let _ = stream.write_all(response.as_bytes());
+  let _ = stream.flush();
   // Give the browser time to receive the response before closing
   std::thread::sleep(std::time::Duration::from_millis(100));
 }
 
+fn respond_with_not_found(mut stream: TcpStream) {
+  let body = "404 - Not Found";
+  let response = format!(
+    "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+    body.len(),
+    body
+  );
+
+  let _ = stream.write_all(response.as_bytes());
+  let _ = stream.flush();
+}
+
 fn respond_with_error(error_message: String, mut stream: TcpStream) {
   println!("Error: {}", error_message);
   let body = format!("400 - Bad Request - {}", error_message);
@@ -93,7 +141,34 @@ fn respond_with_error(error_message: String, mut stream: TcpStream) {
     body
   );
 
-  stream.write_all(response.as_bytes()).unwrap();
-  stream.flush().unwrap();
+  let _ = stream.write_all(response.as_bytes());
+  let _ = stream.flush();
   std::thread::sleep(std::time::Duration::from_millis(100));
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_redirect_path() {
+    assert_eq!(redirect_path("http://127.0.0.1:8888/callback"), "/callback");
+    assert_eq!(
+      redirect_path("http://127.0.0.1:8888/auth/cb?x=1"),
+      "/auth/cb"
+    );
+    assert_eq!(redirect_path("http://127.0.0.1:8888"), "/");
+  }
+
+  #[test]
+  fn test_is_callback_request() {
+    assert!(is_callback_request(
+      "/callback?code=abc&state=xyz",
+      "/callback"
+    ));
+    assert!(is_callback_request("/callback", "/callback"));
+    assert!(!is_callback_request("/favicon.ico", "/callback"));
+    assert!(!is_callback_request("/", "/callback"));
+    assert!(!is_callback_request("/callbackx?code=abc", "/callback"));
+  }
+}